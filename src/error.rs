@@ -13,12 +13,17 @@ pub enum Errors {
     NoVideos,
     ValidationError(OpValidationResult),
     IOError(ErrorKind),
+    VerificationFailed(PathBuf),
 }
 
 #[derive(Debug, Clone)]
 pub enum OpValidationResult {
     Valid,
     OverlappingRange(MapOp, MapOp),
+    NameCollision(MapOp, String),
+    /// `op` matches two source files from different camera folders that share the same `id`
+    /// (e.g. `100CANON/MVI_0001.MOV` and `101GOPRO/GOPR0001.MP4` both restart numbering at 1).
+    DuplicateId(MapOp, u32),
     Empty,
 }
 
@@ -42,11 +47,26 @@ impl Display for Errors {
                         "{} ({}..{}) overlaps {} ({}..{})",
                         op1.name, op1.start, op1.end, op2.name, op2.start, op2.end
                     ),
+                    NameCollision(op, name) => write!(
+                        f,
+                        "{} produces the output filename {:#?} more than once",
+                        op.name, name
+                    ),
+                    DuplicateId(op, id) => write!(
+                        f,
+                        "{} matches id {} in more than one camera folder; narrow its range or kind filter",
+                        op.name, id
+                    ),
                     Empty => write!(f, "No operations defined! Exiting."),
                     Valid => panic!(),
                 }
             }
             IOError(kind) => write!(f, "Unhandled IO error: {:?}", kind),
+            VerificationFailed(path) => write!(
+                f,
+                "{:#?} failed checksum verification after copying!",
+                path
+            ),
         }
     }
 }
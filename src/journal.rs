@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::error::{Errors, Result};
+
+/// Name of the on-disk journal written into an output directory so an interrupted
+/// [`crate::mapper::Mapper::execute`] can be resumed instead of restarted from scratch.
+pub const JOURNAL_FILENAME: &str = ".dumpman-journal.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskState {
+    Pending,
+    InProgress,
+    Done,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalTask {
+    pub op_name: String,
+    /// Name of the `DCIM/*` subfolder the source file was found in. `media_id` alone isn't a
+    /// stable identity: different cards/vendors commonly restart numbering at 1, so two distinct
+    /// files can share an id once multiple camera folders are involved.
+    pub camera_folder: String,
+    pub media_id: u32,
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub byte_size: u64,
+    pub state: TaskState,
+    /// blake3 digest of the destination file, recorded once `--verify` confirms it matches the
+    /// source. Absent on journals from before verification existed or when `--verify` is off.
+    #[serde(default)]
+    pub digest: Option<String>,
+}
+
+/// The full set of planned tasks for an `execute()` run, persisted to
+/// `out_path/.dumpman-journal.json` so progress survives a crash or `Ctrl-C`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Journal {
+    pub tasks: Vec<JournalTask>,
+}
+
+impl Journal {
+    pub fn path(out_path: &Path) -> PathBuf {
+        out_path.join(JOURNAL_FILENAME)
+    }
+
+    /// Loads the journal from `out_path`, if one exists.
+    pub fn load(out_path: &Path) -> Result<Option<Self>> {
+        let path = Self::path(out_path);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let file = File::open(&path).map_err(|e| Errors::IOError(e.kind()))?;
+        let journal = serde_json::from_reader(BufReader::new(file))
+            .map_err(|_| Errors::IOError(std::io::ErrorKind::InvalidData))?;
+        Ok(Some(journal))
+    }
+
+    /// Writes the journal to `out_path`, fsyncing it so a task marked `Done` is durable once
+    /// this call returns.
+    pub fn save(&self, out_path: &Path) -> Result {
+        let path = Self::path(out_path);
+        let file = File::create(&path).map_err(|e| Errors::IOError(e.kind()))?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, self)
+            .map_err(|_| Errors::IOError(std::io::ErrorKind::Other))?;
+        writer.flush().map_err(|e| Errors::IOError(e.kind()))?;
+        writer.get_ref().sync_all().map_err(|e| Errors::IOError(e.kind()))?;
+        Ok(())
+    }
+
+    pub fn task_mut(
+        &mut self,
+        op_name: &str,
+        camera_folder: &str,
+        media_id: u32,
+    ) -> Option<&mut JournalTask> {
+        self.tasks.iter_mut().find(|t| {
+            t.op_name == op_name && t.camera_folder == camera_folder && t.media_id == media_id
+        })
+    }
+
+    pub fn has_task(&self, op_name: &str, camera_folder: &str, media_id: u32) -> bool {
+        self.tasks.iter().any(|t| {
+            t.op_name == op_name && t.camera_folder == camera_folder && t.media_id == media_id
+        })
+    }
+}
@@ -1,15 +1,49 @@
 use clap::Parser;
 use log::debug;
+use std::{path::PathBuf, str::FromStr};
 use strum::VariantNames;
 
-use crate::mapper::{MapOpType, Mapper};
+use crate::mapper::{MapOpType, Mapper, DEFAULT_SESSION_GAP_SECS};
 
 const DEFAULT_ROOT: &str = ".";
 
 pub mod error;
+pub mod journal;
+pub mod manifest;
 pub mod mapper;
 pub mod util;
 
+/// Selects how `--auto` groups media into ops without prompting for ranges interactively.
+#[derive(Debug, Clone)]
+pub enum AutoMode {
+    /// Bucket by UTC calendar date.
+    Day,
+    /// Start a new group whenever the gap since the previous clip exceeds this many seconds.
+    Session { gap_secs: u64 },
+}
+
+impl FromStr for AutoMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(AutoMode::Day),
+            "session" => Ok(AutoMode::Session {
+                gap_secs: DEFAULT_SESSION_GAP_SECS,
+            }),
+            s => match s.strip_prefix("gap:") {
+                Some(secs) => secs
+                    .parse()
+                    .map(|gap_secs| AutoMode::Session { gap_secs })
+                    .map_err(|_| format!("{secs:?} is not a valid number of seconds")),
+                None => Err(format!(
+                    "{s:?} is not a valid auto mode (expected day, session, or gap:SECONDS)"
+                )),
+            },
+        }
+    }
+}
+
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 pub struct Args {
@@ -21,13 +55,30 @@ pub struct Args {
     #[clap(short, long, value_parser)]
     out: String,
 
-    /// Enable autogrouping
-    #[clap(short, long, action, default_value_t = false)]
-    auto: bool,
+    /// Enable autogrouping: `day` (bucket by calendar date), `session` (split on a recording
+    /// gap, default 30 min), or `gap:SECONDS` for a custom session gap
+    #[clap(short, long, value_parser)]
+    auto: Option<AutoMode>,
 
     /// Make the output directory if it does not exist
     #[clap(short, long, action, default_value_t = false)]
     mkdir: bool,
+
+    /// Verify each copy by comparing a blake3 checksum of the source and destination
+    #[clap(long, action, default_value_t = false)]
+    verify: bool,
+
+    /// Maximum number of copies to run concurrently
+    #[clap(short, long, value_parser, default_value_t = 4)]
+    jobs: usize,
+
+    /// Load ops from a TOML/JSON manifest instead of prompting or autogrouping
+    #[clap(long, value_parser)]
+    manifest: Option<PathBuf>,
+
+    /// Print the planned source -> destination copies without touching the filesystem
+    #[clap(long, action, default_value_t = false)]
+    dry_run: bool,
 }
 
 fn main() -> Result<(), anyhow::Error> {
@@ -50,13 +101,19 @@ fn main() -> Result<(), anyhow::Error> {
         MapOpType::VARIANTS.join(", ")
     );
 
-    if args.auto {
-        mapper.group_by_day()?;
-    } else {
-        mapper.prompt_for_ops()?;
+    match (&args.manifest, &args.auto) {
+        (Some(path), _) => mapper.load_manifest(manifest::load(path)?)?,
+        (None, Some(AutoMode::Day)) => mapper.group_by_day()?,
+        (None, Some(AutoMode::Session { gap_secs })) => mapper.group_by_session(*gap_secs)?,
+        (None, None) => mapper.prompt_for_ops()?,
+    }
+
+    if args.dry_run {
+        mapper.dry_run();
+        return Ok(());
     }
 
-    mapper.execute()?;
+    mapper.execute(args.jobs, args.verify)?;
 
     Ok(())
 }
@@ -0,0 +1,92 @@
+use serde::Deserialize;
+use std::{fs, path::Path};
+
+use crate::{
+    error::{Errors, Result},
+    mapper::{MapOpType, MediaKind},
+};
+
+/// One entry of a `--manifest` file: the declarative equivalent of a single answer to the
+/// interactive prompts in `Mapper::group_by_day`/`Mapper::prompt_for_ops`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManifestOp {
+    pub op_type: MapOpType,
+    pub name: String,
+    pub start: u32,
+    pub end: u32,
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default)]
+    pub kind: Option<MediaKind>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Manifest {
+    ops: Vec<ManifestOp>,
+}
+
+/// Loads a list of `ManifestOp`s from `path`, parsed as TOML unless the extension is `.json`.
+pub fn load(path: &Path) -> Result<Vec<ManifestOp>> {
+    let contents = fs::read_to_string(path).map_err(|e| Errors::IOError(e.kind()))?;
+
+    let manifest = if path.extension().and_then(|e| e.to_str()) == Some("json") {
+        serde_json::from_str::<Manifest>(&contents)
+            .map_err(|_| Errors::IOError(std::io::ErrorKind::InvalidData))?
+    } else {
+        toml::from_str::<Manifest>(&contents)
+            .map_err(|_| Errors::IOError(std::io::ErrorKind::InvalidData))?
+    };
+
+    Ok(manifest.ops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{io::Write, path::PathBuf};
+
+    fn write_temp(extension: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "dumpman-manifest-test-{:?}.{extension}",
+            std::thread::current().id()
+        ));
+        let mut file = fs::File::create(&path).unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn loads_toml_manifest() {
+        let path = write_temp(
+            "toml",
+            r#"
+            [[ops]]
+            op_type = "copy"
+            name = "day-one"
+            start = 0
+            end = 10
+            "#,
+        );
+        let ops = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].name, "day-one");
+        assert_eq!((ops[0].start, ops[0].end), (0, 10));
+        assert_eq!(ops[0].template, None);
+    }
+
+    #[test]
+    fn loads_json_manifest() {
+        let path = write_temp(
+            "json",
+            r#"{"ops": [{"op_type": "move", "name": "day-two", "start": 10, "end": 20}]}"#,
+        );
+        let ops = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].name, "day-two");
+        assert_eq!(ops[0].op_type, MapOpType::Move);
+    }
+}
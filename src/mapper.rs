@@ -1,21 +1,33 @@
-use chrono::{Date, TimeZone, Utc};
-use lazy_regex::regex;
+use chrono::{Date, DateTime, TimeZone, Utc};
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     ffi::OsString,
     fs,
-    path::PathBuf,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
     str::FromStr,
-    time::{SystemTime, UNIX_EPOCH},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use strum::{EnumString, EnumVariantNames, VariantNames};
 
 use crate::{
     error::{Errors, OpValidationResult, Result},
+    journal::{Journal, JournalTask, TaskState, JOURNAL_FILENAME},
+    manifest::ManifestOp,
     util,
 };
 
-const CONTENT_PATH: [&str; 2] = ["DCIM", "100CANON"];
+const CONTENT_PATH: [&str; 1] = ["DCIM"];
+
+/// Default gap between clips, in seconds, that starts a new session in [`Mapper::group_by_session`].
+pub const DEFAULT_SESSION_GAP_SECS: u64 = 30 * 60;
 
 #[derive(Debug, Clone)]
 pub struct Mapper {
@@ -23,12 +35,20 @@ pub struct Mapper {
     out_path: PathBuf,
     media: Vec<Media>,
     ops: Vec<MapOp>,
+    /// Resumed from a previous `execute()`, if `out_path` held a journal file. Its tasks are not
+    /// checked against the current media or ops.
+    journal: Option<Journal>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, EnumString, EnumVariantNames)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumString, EnumVariantNames, Serialize, Deserialize)]
 #[strum(serialize_all = "kebab_case")]
+#[serde(rename_all = "kebab-case")]
 pub enum MapOpType {
     Copy,
+    /// Relocate each `Media` into the op's output group, optionally renaming it per `template`.
+    Move,
+    /// Rename each `Media` in place (no relocation into an output group) using `template`.
+    Rename,
 }
 
 impl Default for MapOpType {
@@ -37,12 +57,182 @@ impl Default for MapOpType {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, EnumVariantNames, Serialize, Deserialize)]
+#[strum(serialize_all = "kebab_case")]
+#[serde(rename_all = "kebab-case")]
+pub enum MediaKind {
+    Video,
+    Photo,
+    Raw,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MapOp {
     pub(crate) op_type: MapOpType,
     pub(crate) name: String,
     pub(crate) start: u32,
     pub(crate) end: u32,
+    /// Output filename pattern, e.g. `{name}_{id:04}.MOV`. `None` keeps the original filename.
+    pub(crate) template: Option<String>,
+    /// Restricts this op to `Media` of a single kind. `None` matches any kind.
+    pub(crate) kind: Option<MediaKind>,
+}
+
+impl MapOp {
+    fn matches(&self, m: &Media) -> bool {
+        m.id >= self.start && m.id < self.end && self.kind.map_or(true, |k| k == m.kind)
+    }
+}
+
+/// Renders `template` for a single `Media`, substituting `{name}` (the op's name), `{id}` /
+/// `{id:WIDTH}` (the parsed id, optionally zero-padded), `{ext}` (original extension) and
+/// `{date}` (the media's `created_at`, formatted `%Y-%m-%d`). Unknown placeholders pass through
+/// unchanged.
+fn render_template(template: &str, op: &MapOp, m: &Media) -> String {
+    let ext = Path::new(&m.filename)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+    let date = DateTime::<Utc>::from(m.created_at).format("%Y-%m-%d").to_string();
+
+    let mut out = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '{' {
+            out.push(c);
+            continue;
+        }
+
+        let mut token = String::new();
+        for c2 in chars.by_ref() {
+            if c2 == '}' {
+                break;
+            }
+            token.push(c2);
+        }
+
+        let (key, width) = match token.split_once(':') {
+            Some((k, w)) => (k, w.parse::<usize>().ok()),
+            None => (token.as_str(), None),
+        };
+
+        match key {
+            "id" => match width {
+                Some(width) => out.push_str(&format!("{:0width$}", m.id, width = width)),
+                None => out.push_str(&m.id.to_string()),
+            },
+            "name" => out.push_str(&op.name),
+            "ext" => out.push_str(ext),
+            "date" => out.push_str(&date),
+            _ => {
+                out.push('{');
+                out.push_str(&token);
+                out.push('}');
+            }
+        }
+    }
+    out
+}
+
+/// Renders the output filename for `m` under `op`: `op.template` if set, otherwise the media's
+/// original filename.
+fn output_name(op: &MapOp, m: &Media) -> OsString {
+    match &op.template {
+        Some(t) => OsString::from(render_template(t, op, m)),
+        None => m.filename.clone(),
+    }
+}
+
+fn prompt_for_kind() -> Option<MediaKind> {
+    let k = rprompt::prompt_reply_stdout("Filter by media kind (video/photo/raw, empty = any): ")
+        .unwrap();
+    if k.is_empty() {
+        None
+    } else {
+        MediaKind::from_str(&k).ok()
+    }
+}
+
+fn prompt_for_template() -> Option<String> {
+    let t = rprompt::prompt_reply_stdout("Enter filename template (empty = keep original): ")
+        .unwrap();
+    if t.is_empty() {
+        None
+    } else {
+        Some(t)
+    }
+}
+
+/// Moves `from` to `to`, falling back to copy-then-remove when `fs::rename` fails (e.g. because
+/// `from` and `to` are on different filesystems).
+fn move_file(from: &Path, to: &Path) -> Result {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to).map_err(|e| Errors::IOError(e.kind()))?;
+    fs::remove_file(from).map_err(|e| Errors::IOError(e.kind()))?;
+    Ok(())
+}
+
+/// Streams `path` through blake3 and returns the resulting digest as a hex string.
+fn hash_file(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path).map_err(|e| Errors::IOError(e.kind()))?;
+    let mut hasher = blake3::Hasher::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| Errors::IOError(e.kind()))?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+/// MPEG-TS packets are 188 bytes, each starting with a `0x47` sync byte; checking a handful of
+/// them rules out any file that merely *starts* with `0x47` ('G') by coincidence.
+const MPEG_TS_PACKET_LEN: usize = 188;
+const MPEG_TS_SYNC_PACKETS_CHECKED: usize = 4;
+
+/// Sniffs the leading bytes of `path` and classifies it by content rather than extension, so
+/// mixed-vendor cards (Sony `.MTS`, GoPro `.MP4`, raw photos, ...) aren't silently dropped.
+fn detect_kind(path: &Path) -> Option<MediaKind> {
+    let mut buf = [0u8; 12];
+    let mut file = fs::File::open(path).ok()?;
+    let n = file.read(&mut buf).ok()?;
+    let buf = &buf[..n];
+
+    if buf.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(MediaKind::Photo)
+    } else if buf.len() >= 8 && &buf[4..8] == b"ftyp" {
+        Some(MediaKind::Video)
+    } else if buf.starts_with(&[0x47]) && is_mpeg_ts(&mut file) {
+        Some(MediaKind::Video)
+    } else if buf.starts_with(b"II*\0") || buf.starts_with(b"MM\0*") {
+        Some(MediaKind::Raw)
+    } else {
+        None
+    }
+}
+
+/// Confirms the `0x47` sync byte repeats every `MPEG_TS_PACKET_LEN` bytes for a few packets,
+/// rather than trusting a single leading byte that any text file could share by coincidence.
+fn is_mpeg_ts(file: &mut fs::File) -> bool {
+    let mut sync = [0u8; 1];
+    for i in 0..MPEG_TS_SYNC_PACKETS_CHECKED {
+        let offset = (i * MPEG_TS_PACKET_LEN) as u64;
+        if file.seek(SeekFrom::Start(offset)).is_err() {
+            return false;
+        }
+        match file.read(&mut sync) {
+            Ok(1) if sync[0] == 0x47 => continue,
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// Pulls the trailing run of digits out of a filename stem (e.g. `MVI_0042` -> `42`,
+/// `GOPR1234` -> `1234`) so files can be sorted/ranged without assuming a fixed prefix format.
+fn extract_sequence(stem: &str) -> Option<u32> {
+    let digits: String = stem.chars().rev().take_while(|c| c.is_ascii_digit()).collect();
+    if digits.is_empty() {
+        return None;
+    }
+    digits.chars().rev().collect::<String>().parse().ok()
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -50,6 +240,9 @@ pub struct Media {
     id: u32,
     filename: OsString,
     created_at: SystemTime,
+    kind: MediaKind,
+    /// Name of the `DCIM/*` subfolder this file was found in, e.g. `100CANON`.
+    camera_folder: String,
 }
 
 impl PartialOrd for Media {
@@ -81,11 +274,22 @@ impl Mapper {
         } else {
             match fs::read_dir(&out_path) {
                 Ok(it) => {
+                    let mut has_journal = false;
+                    let mut has_other_entries = false;
                     for f in it {
-                        if f.unwrap().file_name().eq_ignore_ascii_case(".ds_store") {
+                        let name = f.map_err(|e| Errors::IOError(e.kind()))?.file_name();
+                        if name.eq_ignore_ascii_case(".ds_store") {
                             continue;
+                        } else if name == JOURNAL_FILENAME {
+                            has_journal = true;
+                        } else {
+                            has_other_entries = true;
                         }
+                    }
 
+                    // A journal on disk is left by a previous run (it is kept even after every
+                    // task is Done); resume it instead of refusing to touch a "non-empty" directory.
+                    if has_other_entries && !has_journal {
                         return Err(Errors::OutputDirectoryNotEmpty);
                     }
                 }
@@ -95,38 +299,67 @@ impl Mapper {
             }
         }
 
+        let journal = Journal::load(&out_path)?;
+
         Ok(Self {
             root_path,
             out_path,
             media: Vec::new(),
             ops: Vec::new(),
+            journal,
         })
     }
 
     pub fn load_media(&mut self) -> Result {
-        let re = regex!(r#"MVI_(\d{4})\.MOV"#);
-        let dir = match fs::read_dir(&self.root_path) {
-            Ok(dir) => dir,
-            Err(e) => return Err(Errors::IOError(e.kind())),
-        };
-        let mut media = dir
-            .filter_map(|entry| {
-                let entry = entry.unwrap();
-                if entry.file_type().unwrap().is_file() {
-                    let filename = entry.file_name().clone();
-                    let caps = re.captures(filename.to_str().unwrap());
-                    caps.map(|cap| Media {
-                        id: cap.get(1).unwrap().as_str().parse().unwrap(),
-                        filename: filename.clone(),
-                        created_at: entry.metadata().unwrap().created().unwrap(),
-                    })
-                } else {
-                    None
+        let dcim = fs::read_dir(&self.root_path).map_err(|e| Errors::IOError(e.kind()))?;
+
+        let mut media = Vec::new();
+        for camera_dir in dcim {
+            let camera_dir = camera_dir.map_err(|e| Errors::IOError(e.kind()))?;
+            if !camera_dir
+                .file_type()
+                .map_err(|e| Errors::IOError(e.kind()))?
+                .is_dir()
+            {
+                continue;
+            }
+            let camera_folder = camera_dir.file_name().to_string_lossy().into_owned();
+
+            let entries = fs::read_dir(camera_dir.path()).map_err(|e| Errors::IOError(e.kind()))?;
+            for entry in entries {
+                let entry = entry.map_err(|e| Errors::IOError(e.kind()))?;
+                if !entry.file_type().map_err(|e| Errors::IOError(e.kind()))?.is_file() {
+                    continue;
                 }
-            })
-            .collect::<Vec<_>>();
 
-        if media.len() == 0 {
+                let Some(kind) = detect_kind(&entry.path()) else {
+                    continue;
+                };
+
+                let filename = entry.file_name();
+                let stem = Path::new(&filename)
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or_default();
+                let Some(id) = extract_sequence(stem) else {
+                    continue;
+                };
+
+                media.push(Media {
+                    id,
+                    filename,
+                    created_at: entry
+                        .metadata()
+                        .map_err(|e| Errors::IOError(e.kind()))?
+                        .created()
+                        .map_err(|e| Errors::IOError(e.kind()))?,
+                    kind,
+                    camera_folder: camera_folder.clone(),
+                });
+            }
+        }
+
+        if media.is_empty() {
             Err(Errors::NoVideos)
         } else {
             media.sort_unstable();
@@ -163,14 +396,51 @@ impl Mapper {
                     continue;
                 }
 
-                if (op1.start > op2.start && op2.end > op1.start)
-                    || (op1.start < op2.start && op1.end > op2.start)
+                // An id range overlap is only a real conflict if some Media could match both
+                // ops' kind filters; e.g. a Video-only op and a Photo-only op are free to share
+                // a range since `MapOp::matches` already keeps them disjoint per-file.
+                let kinds_can_collide = op1.kind.map_or(true, |k| op2.kind.map_or(true, |k2| k == k2));
+
+                if kinds_can_collide
+                    && ((op1.start > op2.start && op2.end > op1.start)
+                        || (op1.start < op2.start && op1.end > op2.start))
                 {
                     return Ok(OverlappingRange(op1, op2));
                 }
             }
         }
 
+        for op in &self.ops {
+            let mut seen = std::collections::HashSet::<OsString>::new();
+            for m in &self.media {
+                if op.matches(m) {
+                    let name = output_name(op, m);
+                    if !seen.insert(name.clone()) {
+                        return Ok(NameCollision(op.clone(), name.to_string_lossy().into_owned()));
+                    }
+                }
+            }
+        }
+
+        // `id` alone isn't unique across camera folders (two cards restarting numbering at 1
+        // both land in the same op), so check that separately from the rendered-name collision
+        // above, which only catches it if the template also happens to collide.
+        for op in &self.ops {
+            let mut seen = std::collections::HashMap::<u32, &str>::new();
+            for m in &self.media {
+                if op.matches(m) {
+                    match seen.get(&m.id) {
+                        Some(camera_folder) if *camera_folder != m.camera_folder => {
+                            return Ok(DuplicateId(op.clone(), m.id));
+                        }
+                        _ => {
+                            seen.insert(m.id, &m.camera_folder);
+                        }
+                    }
+                }
+            }
+        }
+
         Ok(Valid)
     }
 
@@ -209,11 +479,105 @@ impl Mapper {
                     Default::default()
                 }
             };
+            let template = prompt_for_template();
+            let kind = prompt_for_kind();
+
             v.push(MapOp {
                 end: bounds.1,
                 name,
                 op_type,
                 start: bounds.0,
+                template,
+                kind,
+            });
+        }
+
+        self.ops.extend(v);
+
+        let valid = self.validate_ops()?;
+
+        match &valid {
+            OpValidationResult::Valid => {}
+            _ => return Err(Errors::ValidationError(valid)),
+        }
+
+        Ok(())
+    }
+
+    /// Auto-groups `Media` into recording sessions instead of calendar days: sorted by
+    /// `created_at`, a new session starts whenever the gap to the previous clip exceeds
+    /// `gap_secs`. This avoids `group_by_day` wrongly merging an evening-then-midnight shoot or
+    /// splitting a single session that crosses midnight.
+    pub fn group_by_session(&mut self, gap_secs: u64) -> Result {
+        let gap = Duration::from_secs(gap_secs);
+
+        let mut sorted = self.media.clone();
+        sorted.sort_by_key(|m| m.created_at);
+
+        struct Session {
+            start_id: u32,
+            end_id: u32,
+            session_start: SystemTime,
+            last_seen: SystemTime,
+        }
+
+        let mut sessions = Vec::<Session>::new();
+        for m in &sorted {
+            let starts_new_session = match sessions.last() {
+                None => true,
+                Some(s) => m
+                    .created_at
+                    .duration_since(s.last_seen)
+                    .unwrap_or_default()
+                    > gap,
+            };
+
+            if starts_new_session {
+                sessions.push(Session {
+                    start_id: m.id,
+                    end_id: m.id + 1,
+                    session_start: m.created_at,
+                    last_seen: m.created_at,
+                });
+            } else {
+                let s = sessions.last_mut().unwrap();
+                if m.id < s.start_id {
+                    s.start_id = m.id;
+                }
+                if m.id >= s.end_id {
+                    s.end_id = m.id + 1;
+                }
+                s.last_seen = m.created_at;
+            }
+        }
+
+        let mut v = Vec::<MapOp>::new();
+        println!("Enter a name for the following sessions.");
+        for session in sessions {
+            let start_fmt = DateTime::<Utc>::from(session.session_start)
+                .format("%Y-%m-%dT%H-%M-%S")
+                .to_string();
+            let prompt = format!("{start_fmt}: ");
+            let name = rprompt::prompt_reply_stdout(&prompt).unwrap();
+            let name = format!("{name}_{start_fmt}");
+            let op_type = {
+                if MapOpType::VARIANTS.len() > 1 {
+                    let type_name = rprompt::prompt_reply_stdout("Enter map operation: ").unwrap();
+                    MapOpType::from_str(&type_name).unwrap()
+                } else {
+                    Default::default()
+                }
+            };
+            let template = prompt_for_template();
+            let kind = prompt_for_kind();
+
+            v.push(MapOp {
+                end: session.end_id,
+                name,
+                op_type,
+                start: session.start_id,
+                template,
+                kind,
             });
         }
 
@@ -255,11 +619,16 @@ impl Mapper {
                 .parse::<u32>()
                 .unwrap();
 
+            let template = prompt_for_template();
+            let kind = prompt_for_kind();
+
             v.push(MapOp {
                 end,
                 name,
                 op_type,
                 start,
+                template,
+                kind,
             });
         }
 
@@ -275,22 +644,365 @@ impl Mapper {
         Ok(())
     }
 
-    pub fn execute(&mut self) -> Result {
+    /// Loads ops straight from a `--manifest` file, bypassing `group_by_day`/`group_by_session`/
+    /// `prompt_for_ops` entirely so ranges are scriptable and reproducible.
+    pub fn load_manifest(&mut self, ops: Vec<ManifestOp>) -> Result {
+        let v: Vec<MapOp> = ops
+            .into_iter()
+            .map(|o| MapOp {
+                op_type: o.op_type,
+                name: o.name,
+                start: o.start,
+                end: o.end,
+                template: o.template,
+                kind: o.kind,
+            })
+            .collect();
+
+        self.ops.extend(v);
+
+        let valid = self.validate_ops()?;
+
+        match &valid {
+            OpValidationResult::Valid => {}
+            _ => return Err(Errors::ValidationError(valid)),
+        }
+
+        Ok(())
+    }
+
+    /// Builds the destination path for `m` under `op`. Purely computes the path; doesn't touch
+    /// the filesystem, so it's safe to call from `dry_run`.
+    fn dest_for(&self, op: &MapOp, m: &Media) -> PathBuf {
+        match op.op_type {
+            MapOpType::Rename => self
+                .root_path
+                .join(&m.camera_folder)
+                .join(output_name(op, m)),
+            MapOpType::Copy | MapOpType::Move => {
+                util::join(&self.out_path, [&op.name]).join(output_name(op, m))
+            }
+        }
+    }
+
+    /// Creates `op`'s output group directory if it doesn't exist yet (idempotent, so resuming is
+    /// safe). A no-op for `Rename`, which writes back into the source camera folder.
+    fn ensure_group_dir(&self, op: &MapOp) -> Result {
+        if matches!(op.op_type, MapOpType::Copy | MapOpType::Move) {
+            let group_out = util::join(&self.out_path, [&op.name]);
+            if !group_out.exists() {
+                fs::create_dir(&group_out).map_err(|e| Errors::IOError(e.kind()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints the exact source -> destination plan for every `Media` covered by `self.ops`,
+    /// plus anything left out of all ranges, without touching the filesystem or the journal.
+    pub fn dry_run(&self) {
         for op in &self.ops {
-            match op.op_type {
-                MapOpType::Copy => {
-                    let group_out = util::join(&self.out_path, [&op.name]);
-                    fs::create_dir(&group_out).unwrap();
-                    for m in self.media.clone() {
-                        if m.id >= op.start && m.id < op.end {
-                            let from = util::join(&self.root_path, [&m.filename]);
-                            let to = util::join(&group_out, [&m.filename]);
-                            fs::copy(from, to).unwrap();
-                        }
-                    }
+            println!("[{}] {:?} ({}..{})", op.name, op.op_type, op.start, op.end);
+            for m in &self.media {
+                if op.matches(m) {
+                    let from = self.root_path.join(&m.camera_folder).join(&m.filename);
+                    let to = self.dest_for(op, m);
+                    println!("  {} -> {}", from.display(), to.display());
+                }
+            }
+        }
+
+        let unmatched: Vec<&Media> = self
+            .media
+            .iter()
+            .filter(|m| !self.ops.iter().any(|op| op.matches(m)))
+            .collect();
+        if !unmatched.is_empty() {
+            println!("Media outside any op's range:");
+            for m in unmatched {
+                println!("  {:?} (id {})", m.filename, m.id);
+            }
+        }
+    }
+
+    /// Copies/moves/renames every `Media` covered by `self.ops`, dispatching the work across a
+    /// thread pool capped at `jobs` concurrent copies and reporting progress on a bar. When
+    /// `verify` is set, each copy's destination is blake3-hashed against its source and the
+    /// digest is recorded on the task; a mismatch aborts with `Errors::VerificationFailed`.
+    pub fn execute(&mut self, jobs: usize, verify: bool) -> Result {
+        let mut journal = self.journal.take().unwrap_or_default();
+
+        // Seed the journal with every task that isn't already tracked from a previous run.
+        for op in self.ops.clone() {
+            for m in self.media.clone() {
+                if op.matches(&m) && !journal.has_task(&op.name, &m.camera_folder, m.id) {
+                    self.ensure_group_dir(&op)?;
+                    let from = self.root_path.join(&m.camera_folder).join(&m.filename);
+                    let to = self.dest_for(&op, &m);
+                    let byte_size = fs::metadata(&from)
+                        .map_err(|e| Errors::IOError(e.kind()))?
+                        .len();
+                    journal.tasks.push(JournalTask {
+                        op_name: op.name.clone(),
+                        camera_folder: m.camera_folder.clone(),
+                        media_id: m.id,
+                        from,
+                        to,
+                        byte_size,
+                        state: TaskState::Pending,
+                        digest: None,
+                    });
                 }
             }
         }
+        journal.save(&self.out_path)?;
+
+        let op_types: HashMap<String, MapOpType> = self
+            .ops
+            .iter()
+            .map(|op| (op.name.clone(), op.op_type.clone()))
+            .collect();
+
+        // Tasks already Done (from this run or a resumed one) are skipped; InProgress tasks are
+        // re-run since we can't know whether they completed before the previous run died.
+        let pending: Vec<usize> = journal
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.state != TaskState::Done)
+            .map(|(i, _)| i)
+            .collect();
+
+        let total_bytes: u64 = pending.iter().map(|&i| journal.tasks[i].byte_size).sum();
+        let pb = ProgressBar::new(total_bytes);
+        pb.set_style(
+            ProgressStyle::with_template(
+                "{bar:30.cyan/blue} {bytes}/{total_bytes} ({bytes_per_sec}) {msg}",
+            )
+            .unwrap()
+            .progress_chars("#>-"),
+        );
+
+        let total_files = pending.len();
+        let files_done = AtomicUsize::new(0);
+        let journal = Mutex::new(journal);
+        let out_path = &self.out_path;
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs.max(1))
+            .build()
+            .map_err(|_| Errors::IOError(std::io::ErrorKind::Other))?;
+
+        pool.install(|| {
+            pending.par_iter().try_for_each(|&idx| -> Result {
+                let (op_name, from, to) = {
+                    let mut journal = journal.lock().unwrap();
+                    let task = &mut journal.tasks[idx];
+                    task.state = TaskState::InProgress;
+                    (task.op_name.clone(), task.from.clone(), task.to.clone())
+                };
+                journal.lock().unwrap().save(out_path)?;
+
+                // Hash the source before the op runs: a Move/Rename renames `from` away, so
+                // reading it afterwards would fail.
+                let src_hash = if verify { Some(hash_file(&from)?) } else { None };
+
+                match op_types.get(&op_name) {
+                    Some(&MapOpType::Copy) => {
+                        fs::copy(&from, &to).map_err(|e| Errors::IOError(e.kind()))?;
+                    }
+                    Some(&MapOpType::Move) | Some(&MapOpType::Rename) | None => {
+                        move_file(&from, &to)?;
+                    }
+                }
+
+                let digest = if let Some(src_hash) = src_hash {
+                    let dst_hash = hash_file(&to)?;
+                    if src_hash != dst_hash {
+                        return Err(Errors::VerificationFailed(to.clone()));
+                    }
+                    Some(dst_hash)
+                } else {
+                    None
+                };
+
+                let byte_size = {
+                    let mut journal = journal.lock().unwrap();
+                    let task = &mut journal.tasks[idx];
+                    task.state = TaskState::Done;
+                    task.digest = digest;
+                    let byte_size = task.byte_size;
+                    journal.save(out_path)?;
+                    byte_size
+                };
+
+                let done = files_done.fetch_add(1, Ordering::SeqCst) + 1;
+                pb.set_message(format!("{done}/{total_files} files"));
+                pb.inc(byte_size);
+
+                Ok(())
+            })
+        })?;
+
+        pb.finish_with_message(format!("{total_files}/{total_files} files"));
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn media(id: u32, camera_folder: &str, kind: MediaKind) -> Media {
+        Media {
+            id,
+            filename: OsString::from(format!("IMG_{id:04}.JPG")),
+            created_at: SystemTime::UNIX_EPOCH,
+            kind,
+            camera_folder: camera_folder.to_owned(),
+        }
+    }
+
+    fn op(name: &str, start: u32, end: u32) -> MapOp {
+        MapOp {
+            op_type: MapOpType::Copy,
+            name: name.to_owned(),
+            start,
+            end,
+            template: None,
+            kind: None,
+        }
+    }
+
+    fn mapper_with(ops: Vec<MapOp>, media: Vec<Media>) -> Mapper {
+        Mapper {
+            root_path: PathBuf::new(),
+            out_path: PathBuf::new(),
+            media,
+            ops,
+            journal: None,
+        }
+    }
+
+    #[test]
+    fn validate_ops_empty_is_empty() {
+        let mapper = mapper_with(Vec::new(), Vec::new());
+        assert!(matches!(mapper.validate_ops().unwrap(), OpValidationResult::Empty));
+    }
+
+    #[test]
+    fn validate_ops_detects_overlapping_range() {
+        let mapper = mapper_with(vec![op("a", 0, 10), op("b", 5, 15)], Vec::new());
+        assert!(matches!(
+            mapper.validate_ops().unwrap(),
+            OpValidationResult::OverlappingRange(_, _)
+        ));
+    }
+
+    #[test]
+    fn validate_ops_allows_overlapping_range_with_disjoint_kinds() {
+        let mapper = mapper_with(
+            vec![
+                MapOp {
+                    kind: Some(MediaKind::Video),
+                    ..op("videos", 0, 500)
+                },
+                MapOp {
+                    kind: Some(MediaKind::Photo),
+                    ..op("photos", 100, 600)
+                },
+            ],
+            Vec::new(),
+        );
+        assert!(matches!(mapper.validate_ops().unwrap(), OpValidationResult::Valid));
+    }
+
+    #[test]
+    fn validate_ops_detects_name_collision() {
+        let mapper = mapper_with(
+            vec![op("a", 0, 10)],
+            vec![
+                media(1, "100CANON", MediaKind::Photo),
+                media(1, "101GOPRO", MediaKind::Photo),
+            ],
+        );
+        // Both media share the same rendered name (no template -> original filename, which is
+        // also identical here), so this should be caught before the duplicate-id check below.
+        assert!(matches!(
+            mapper.validate_ops().unwrap(),
+            OpValidationResult::NameCollision(_, _)
+        ));
+    }
+
+    #[test]
+    fn validate_ops_detects_duplicate_id_across_camera_folders() {
+        let mapper = mapper_with(
+            vec![op("a", 0, 10)],
+            vec![
+                Media {
+                    filename: OsString::from("MVI_0001.MOV"),
+                    ..media(1, "100CANON", MediaKind::Video)
+                },
+                Media {
+                    filename: OsString::from("GOPR0001.MP4"),
+                    ..media(1, "101GOPRO", MediaKind::Video)
+                },
+            ],
+        );
+        assert!(matches!(
+            mapper.validate_ops().unwrap(),
+            OpValidationResult::DuplicateId(_, 1)
+        ));
+    }
+
+    #[test]
+    fn validate_ops_allows_repeated_id_within_same_camera_folder() {
+        // Not a realistic case (ids are unique within a folder), but confirms the check keys on
+        // camera_folder rather than flagging every repeated id.
+        let mapper = mapper_with(
+            vec![op("a", 0, 10)],
+            vec![
+                Media {
+                    filename: OsString::from("MVI_0001.MOV"),
+                    ..media(1, "100CANON", MediaKind::Video)
+                },
+                Media {
+                    filename: OsString::from("MVI_0001_2.MOV"),
+                    ..media(1, "100CANON", MediaKind::Video)
+                },
+            ],
+        );
+        assert!(matches!(mapper.validate_ops().unwrap(), OpValidationResult::Valid));
+    }
+
+    #[test]
+    fn render_template_substitutes_known_placeholders() {
+        let op = op("birthday", 0, 10);
+        let m = Media {
+            filename: OsString::from("IMG_0042.JPG"),
+            ..media(42, "100CANON", MediaKind::Photo)
+        };
+        assert_eq!(
+            render_template("{name}_{id:04}.{ext}", &op, &m),
+            "birthday_0042.JPG"
+        );
+    }
+
+    #[test]
+    fn render_template_passes_through_unknown_placeholders() {
+        let op = op("a", 0, 10);
+        let m = media(1, "100CANON", MediaKind::Photo);
+        assert_eq!(render_template("{nope}", &op, &m), "{nope}");
+    }
+
+    #[test]
+    fn extract_sequence_reads_trailing_digits() {
+        assert_eq!(extract_sequence("MVI_0042"), Some(42));
+        assert_eq!(extract_sequence("GOPR1234"), Some(1234));
+    }
+
+    #[test]
+    fn extract_sequence_none_without_trailing_digits() {
+        assert_eq!(extract_sequence("THUMBS"), None);
+    }
+}